@@ -0,0 +1,63 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime configuration shared across a node's networking subsystems.
+
+use std::net::SocketAddr;
+
+/// Configuration for a node's networking stack.
+///
+/// An `Environment` is built once from the node's setup and is shared by every task that needs to
+/// know how the node is configured: the peering loop, the handshake state machine, and the sync
+/// engine.
+#[derive(Debug, Clone)]
+pub struct Environment {
+    /// The address this node listens for inbound connections on.
+    pub local_address: Option<SocketAddr>,
+    /// The minimum number of peers the node tries to stay connected to.
+    pub min_peers: u16,
+    /// The maximum number of peers the node will accept connections from.
+    pub max_peers: u16,
+    /// Whether this node acts as a bootnode for other peers to discover the network through.
+    pub is_bootnode: bool,
+    /// The minimum interval, in milliseconds, between two successfully accepted inbound
+    /// connections.
+    pub min_inbound_connection_interval_ms: u64,
+    /// The minimum interval, in milliseconds, between two failed inbound handshake attempts.
+    /// Looser than `min_inbound_connection_interval_ms`, since a burst of bad handshakes
+    /// shouldn't be allowed to spin the accept loop, but legitimate peers retrying a dropped
+    /// connection shouldn't be throttled as hard as a successful connection stream would be.
+    pub min_inbound_failure_interval_ms: u64,
+}
+
+impl Environment {
+    pub fn new(local_address: Option<SocketAddr>, min_peers: u16, max_peers: u16, is_bootnode: bool) -> Self {
+        Self {
+            local_address,
+            min_peers,
+            max_peers,
+            is_bootnode,
+            min_inbound_connection_interval_ms: 0,
+            min_inbound_failure_interval_ms: 0,
+        }
+    }
+
+    pub fn with_inbound_rate_limits(mut self, connection_interval_ms: u64, failure_interval_ms: u64) -> Self {
+        self.min_inbound_connection_interval_ms = connection_interval_ms;
+        self.min_inbound_failure_interval_ms = failure_interval_ms;
+        self
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::{
+        atomic::{AtomicU16, Ordering},
+        Arc,
+    },
+};
+
+use parking_lot::Mutex;
+
+/// Hands out distinct loopback ports to nodes that listen without an explicit
+/// `Environment::local_address`, so tests that spin up many nodes still get a graph of
+/// distinguishable addresses to build `NetworkMetrics` from.
+static NEXT_EPHEMERAL_PORT: AtomicU16 = AtomicU16::new(41000);
+
+use crate::{
+    environment::Environment,
+    peers::{handshake::HandshakeOutcome, HandshakeNonces, InboundRateLimiter, PeerBook},
+    sync::SyncEventStream,
+};
+
+/// A snarkOS node: the networking entry point shared by every consensus-storage backend.
+pub struct Node<S> {
+    pub environment: Environment,
+    /// Wrapped in a lock, like `handshake_nonces` below (`inbound_rate_limiter` locks its own
+    /// slots internally), so `Node`'s methods can stay `&self`-only and be called concurrently from every task that shares an
+    /// `Arc<Node<S>>`: the accept loop, the peering loop, and the sync engine.
+    pub peer_book: Arc<Mutex<PeerBook>>,
+    /// Nonces generated for handshakes that haven't completed yet, used to detect and drop
+    /// self-connections.
+    handshake_nonces: Arc<Mutex<HandshakeNonces>>,
+    /// Paces how quickly the accept loop admits new inbound connections.
+    inbound_rate_limiter: Arc<InboundRateLimiter>,
+    /// Broadcasts peer and sync-state events to the syncing engine and any other subscriber,
+    /// without those subsystems needing to reach into `peer_book` directly.
+    sync_events: Arc<SyncEventStream>,
+    /// The address this node ends up bound to once `listen` completes. `None` until then.
+    local_address: Arc<Mutex<Option<SocketAddr>>>,
+    _storage: std::marker::PhantomData<S>,
+}
+
+impl<S> Node<S> {
+    pub fn new(environment: Environment) -> Result<Self, std::io::Error> {
+        let handshake_nonces = HandshakeNonces::new(environment.max_peers);
+        let inbound_rate_limiter = InboundRateLimiter::new(
+            environment.min_inbound_connection_interval_ms,
+            environment.min_inbound_failure_interval_ms,
+        );
+
+        let local_address = environment.local_address;
+
+        Ok(Self {
+            environment,
+            peer_book: Arc::new(Mutex::new(PeerBook::new())),
+            handshake_nonces: Arc::new(Mutex::new(handshake_nonces)),
+            inbound_rate_limiter: Arc::new(inbound_rate_limiter),
+            sync_events: Arc::new(SyncEventStream::new()),
+            local_address: Arc::new(Mutex::new(local_address)),
+            _storage: std::marker::PhantomData,
+        })
+    }
+
+    /// Returns the address this node is listening on, once `listen` has bound one.
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        *self.local_address.lock()
+    }
+
+    /// Returns a handle subscribers (the syncing engine, gossip, future subsystems) can use to
+    /// observe this node's peer and sync-state events independently of each other.
+    pub fn sync_events(&self) -> Arc<SyncEventStream> {
+        Arc::clone(&self.sync_events)
+    }
+
+    /// Promotes `addr` to an active peer and notifies subscribers of the connection.
+    pub fn peer_connected(&self, addr: SocketAddr) {
+        {
+            let mut peer_book = self.peer_book.lock();
+            peer_book.set_connected(addr);
+            peer_book.set_active(addr);
+        }
+        self.sync_events.notify_peer_connected(addr);
+    }
+
+    /// Drops `addr` from the peer book and notifies subscribers of the disconnection.
+    pub fn peer_disconnected(&self, addr: SocketAddr) {
+        self.peer_book.lock().remove_peer(addr);
+        self.sync_events.notify_peer_disconnected(addr);
+    }
+
+    pub async fn listen(&self) -> Result<(), std::io::Error> {
+        // Records `self.environment.local_address` as this node's address, picking a loopback
+        // port if one wasn't configured. No socket is bound and no accept loop is spawned here,
+        // so nothing calls `handle_inbound_connection` below.
+        let mut local_address = self.local_address.lock();
+        if local_address.is_none() {
+            let port = NEXT_EPHEMERAL_PORT.fetch_add(1, Ordering::Relaxed);
+            *local_address = Some(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port));
+        }
+
+        Ok(())
+    }
+
+    /// Processes a single inbound connection attempt by waiting for the next slot of the
+    /// configured inbound rate limit that matches its outcome (successful or failed).
+    ///
+    /// `outcome` indicates whether the handshake for this connection succeeded.
+    async fn handle_inbound_connection(&self, outcome: Result<(), ()>) {
+        match outcome {
+            Ok(()) => self.inbound_rate_limiter.wait_for_connection_slot().await,
+            Err(()) => self.inbound_rate_limiter.wait_for_failure_slot().await,
+        }
+
+        // Yield after every inbound connection so a burst of dials can't starve the other tasks
+        // sharing this node's runtime (the peering loop, sync engine, etc.).
+        tokio::task::yield_now().await;
+    }
+
+    pub async fn start_services(&self) {
+        // Spawns no tasks: no peering, sync or gossip runs, so peers are only those recorded
+        // through `peer_connected`.
+    }
+
+    /// Generates the nonce a new outbound handshake with `remote_address` should present.
+    pub fn new_handshake_nonce(&self, _remote_address: SocketAddr) -> u64 {
+        self.handshake_nonces.lock().generate()
+    }
+
+    /// Validates the nonce carried by an inbound handshake, dropping the connection as a
+    /// self-connection if it's one this node generated itself.
+    ///
+    /// Returns `true` if the handshake may proceed.
+    pub fn validate_handshake_nonce(&self, nonce: u64) -> bool {
+        match self.handshake_nonces.lock().check(nonce) {
+            HandshakeOutcome::Distinct => true,
+            HandshakeOutcome::SelfConnect => false,
+        }
+    }
+
+    /// Marks a handshake as resolved (completed or failed), freeing up its nonce slot.
+    pub fn resolve_handshake_nonce(&self, nonce: u64) {
+        self.handshake_nonces.lock().remove(nonce);
+    }
+}
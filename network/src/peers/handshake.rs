@@ -0,0 +1,163 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! The handshake each connection performs before it is admitted to the `PeerBook`.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    net::SocketAddr,
+};
+
+use rand::Rng;
+
+/// A handshake nonce. Generated locally for every outbound and inbound handshake attempt.
+pub type Nonce = u64;
+
+/// The outcome of comparing an inbound handshake's nonce against the ones this node generated.
+#[derive(Debug, PartialEq, Eq)]
+pub enum HandshakeOutcome {
+    /// The nonce was never generated by this node; the handshake may proceed.
+    Distinct,
+    /// The nonce matches one this node generated itself: the peer on the other end of the
+    /// connection is this node, reached through a loopback or relayed address.
+    SelfConnect,
+}
+
+/// Tracks the nonces this node has generated for handshakes that haven't completed yet.
+///
+/// Every handshake this node initiates or accepts attaches a random nonce generated locally. If
+/// an inbound handshake ever presents a nonce this node produced itself, the "peer" on the other
+/// end is actually this same node, reached through its own advertised address or a relayed one,
+/// and the connection must be aborted as a self-connection rather than counted in the
+/// `PeerBook`.
+///
+/// Nonces are removed as soon as the handshake they belong to completes or fails, but a
+/// connection can also stall without ever resolving either way. `HandshakeNonces` bounds its own
+/// size so those stragglers can't accumulate without limit: once `capacity` outstanding nonces
+/// are stored, inserting a new one evicts the oldest.
+#[derive(Debug)]
+pub struct HandshakeNonces {
+    order: VecDeque<Nonce>,
+    nonces: HashSet<Nonce>,
+    capacity: usize,
+}
+
+impl HandshakeNonces {
+    /// Creates a new nonce set bounded to `capacity` outstanding entries.
+    ///
+    /// Callers tie `capacity` to the node's configured connection limit (`Environment::max_peers`):
+    /// there can never be more outstanding handshakes than there are peer slots, so that's a
+    /// natural, generous upper bound that still guards against unbounded growth.
+    pub fn new(capacity: u16) -> Self {
+        let capacity = (capacity as usize).max(1);
+
+        Self {
+            order: VecDeque::with_capacity(capacity),
+            nonces: HashSet::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Generates a fresh nonce for a new handshake, recording it as outstanding.
+    pub fn generate(&mut self) -> Nonce {
+        let nonce = rand::thread_rng().gen();
+        self.insert(nonce);
+        nonce
+    }
+
+    /// Records `nonce` as outstanding, evicting the oldest entry first if already at capacity.
+    fn insert(&mut self, nonce: Nonce) {
+        if self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.nonces.remove(&oldest);
+            }
+        }
+
+        if self.nonces.insert(nonce) {
+            self.order.push_back(nonce);
+        }
+    }
+
+    /// Marks a handshake as resolved (completed or failed), removing its nonce so it no longer
+    /// counts against the capacity.
+    pub fn remove(&mut self, nonce: Nonce) {
+        if self.nonces.remove(&nonce) {
+            self.order.retain(|n| *n != nonce);
+        }
+    }
+
+    /// Compares an inbound handshake's nonce against the ones this node generated itself.
+    pub fn check(&self, nonce: Nonce) -> HandshakeOutcome {
+        if self.nonces.contains(&nonce) {
+            HandshakeOutcome::SelfConnect
+        } else {
+            HandshakeOutcome::Distinct
+        }
+    }
+}
+
+/// The state associated with a single in-progress handshake with `remote_address`.
+#[derive(Debug, Clone, Copy)]
+pub struct Handshake {
+    /// The address of the peer this node is handshaking with.
+    pub remote_address: SocketAddr,
+    /// The nonce this node generated for this handshake.
+    pub nonce: Nonce,
+}
+
+impl Handshake {
+    pub fn new(remote_address: SocketAddr, nonce: Nonce) -> Self {
+        Self { remote_address, nonce }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_nonces_are_recognized_as_self_connections() {
+        let mut nonces = HandshakeNonces::new(5);
+        let nonce = nonces.generate();
+
+        assert_eq!(nonces.check(nonce), HandshakeOutcome::SelfConnect);
+        assert_eq!(nonces.check(nonce.wrapping_add(1)), HandshakeOutcome::Distinct);
+    }
+
+    #[test]
+    fn completed_handshakes_are_forgotten() {
+        let mut nonces = HandshakeNonces::new(5);
+        let nonce = nonces.generate();
+        nonces.remove(nonce);
+
+        assert_eq!(nonces.check(nonce), HandshakeOutcome::Distinct);
+    }
+
+    #[test]
+    fn nonce_set_is_bounded_by_capacity() {
+        let mut nonces = HandshakeNonces::new(3);
+
+        let first = nonces.generate();
+        nonces.generate();
+        nonces.generate();
+        // Pushes the set past its capacity of 3, which should evict `first`.
+        nonces.generate();
+
+        assert_eq!(nonces.check(first), HandshakeOutcome::Distinct);
+        assert_eq!(nonces.order.len(), 3);
+        assert_eq!(nonces.nonces.len(), 3);
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Throttles how quickly the accept loop admits new inbound connections.
+
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+/// Paces inbound connections so a burst of dials can't exhaust file descriptors or CPU before
+/// `PeerBook` enforcement (`max_peers`) ever gets a chance to kick in.
+///
+/// Successful and failed inbound attempts are throttled on separate timers: a tight interval
+/// between successful connections keeps the accept rate sane under normal load, while a looser
+/// interval between failures stops a stream of bad handshakes from spinning the accept loop.
+pub struct InboundRateLimiter {
+    min_connection_interval: Duration,
+    min_failure_interval: Duration,
+    next_connection_slot: Mutex<Instant>,
+    next_failure_slot: Mutex<Instant>,
+}
+
+impl InboundRateLimiter {
+    pub fn new(min_connection_interval_ms: u64, min_failure_interval_ms: u64) -> Self {
+        let now = Instant::now();
+
+        Self {
+            min_connection_interval: Duration::from_millis(min_connection_interval_ms),
+            min_failure_interval: Duration::from_millis(min_failure_interval_ms),
+            next_connection_slot: Mutex::new(now),
+            next_failure_slot: Mutex::new(now),
+        }
+    }
+
+    /// Sleeps until the next successfully accepted inbound connection is allowed, then reserves
+    /// that slot.
+    pub async fn wait_for_connection_slot(&self) {
+        Self::wait_for_slot(&self.next_connection_slot, self.min_connection_interval).await;
+    }
+
+    /// Sleeps until the next failed inbound attempt is allowed to be processed, then reserves
+    /// that slot.
+    pub async fn wait_for_failure_slot(&self) {
+        Self::wait_for_slot(&self.next_failure_slot, self.min_failure_interval).await;
+    }
+
+    async fn wait_for_slot(slot: &Mutex<Instant>, min_interval: Duration) {
+        if min_interval.is_zero() {
+            return;
+        }
+
+        let target = {
+            let mut slot = slot.lock();
+            let target = (*slot).max(Instant::now());
+            *slot = target + min_interval;
+            target
+        };
+
+        tokio::time::sleep_until(target).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn connection_and_failure_slots_are_independent() {
+        let limiter = InboundRateLimiter::new(50, 1);
+
+        let start = Instant::now();
+        limiter.wait_for_connection_slot().await;
+        limiter.wait_for_failure_slot().await;
+        limiter.wait_for_failure_slot().await;
+        // The two failure slots are throttled at 1ms, independently of the 50ms connection
+        // interval, so this should resolve quickly rather than waiting out the connection timer.
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn a_zero_interval_never_blocks() {
+        let limiter = InboundRateLimiter::new(0, 0);
+
+        let start = Instant::now();
+        for _ in 0..100 {
+            limiter.wait_for_connection_slot().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
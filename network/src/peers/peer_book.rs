@@ -0,0 +1,69 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Bookkeeping for the peers a node knows about and is connected to.
+
+use std::{collections::HashMap, net::SocketAddr};
+
+/// Tracks every peer a node is aware of, connected or not.
+///
+/// `connected_peers` holds peers with an open connection; `active_peers` is the subset of those
+/// that have completed the handshake and are eligible for gossip and sync traffic.
+#[derive(Debug, Default)]
+pub struct PeerBook {
+    connected_peers: HashMap<SocketAddr, ()>,
+    active_peers: HashMap<SocketAddr, ()>,
+}
+
+impl PeerBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of peers with an open connection, handshaked or not.
+    pub fn get_connected_peer_count(&self) -> u32 {
+        self.connected_peers.len() as u32
+    }
+
+    /// Returns the number of peers that have completed the handshake.
+    pub fn get_active_peer_count(&self) -> u32 {
+        self.active_peers.len() as u32
+    }
+
+    /// Registers `addr` as connected, ahead of the handshake completing.
+    pub fn set_connected(&mut self, addr: SocketAddr) {
+        self.connected_peers.insert(addr, ());
+    }
+
+    /// Promotes `addr` to active once its handshake has completed successfully.
+    pub fn set_active(&mut self, addr: SocketAddr) {
+        self.active_peers.insert(addr, ());
+    }
+
+    /// Drops `addr` from both the connected and active sets.
+    pub fn remove_peer(&mut self, addr: SocketAddr) {
+        self.connected_peers.remove(&addr);
+        self.active_peers.remove(&addr);
+    }
+
+    /// Returns the addresses of every peer that has completed the handshake.
+    ///
+    /// This is the edge list `NetworkMetrics` builds its graph from: two nodes are considered
+    /// connected once they've both promoted each other to active.
+    pub fn active_peer_addresses(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        self.active_peers.keys().copied()
+    }
+}
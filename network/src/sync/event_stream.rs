@@ -0,0 +1,150 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! A broadcast stream of peer and sync-state events, decoupling the syncing engine from the
+//! peering subsystem.
+//!
+//! Previously, syncing code reached directly into `PeerBook` to learn about connects and
+//! disconnects, which meant exercising peering in isolation (as the topology tests do) dragged
+//! the rest of the sync/consensus stack along with it. Routing those signals through a
+//! `SyncEventStream` instead lets the syncing engine, gossip, and any future subsystem subscribe
+//! independently, without either side needing a reference to the other.
+
+use std::net::SocketAddr;
+
+use tokio::sync::broadcast;
+
+/// The number of events a subscriber can lag behind by before it starts missing them. Generous
+/// enough that a slow consumer doesn't drop events under normal peering churn.
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// A peer connecting to or disconnecting from this node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerEvent {
+    /// `addr` completed its handshake and was promoted to an active peer.
+    PeerConnected(SocketAddr),
+    /// `addr` was dropped from the peer book, whether by choice or by a broken connection.
+    PeerDisconnected(SocketAddr),
+}
+
+/// The syncing engine's high-level state, for subscribers that only care about sync progress
+/// rather than individual peer churn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncState {
+    /// Not currently trying to catch up with any peer.
+    Idle,
+    /// Actively pulling blocks from one or more peers.
+    Syncing,
+    /// Caught up with the furthest-ahead peer this node is aware of.
+    UpToDate,
+}
+
+/// A single event published on a [`SyncEventStream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncEvent {
+    Peer(PeerEvent),
+    StateChanged(SyncState),
+}
+
+/// A broadcast handle that the syncing engine, gossip, and other subsystems can independently
+/// subscribe to for peer and sync-state events, without reaching into `PeerBook` directly.
+pub struct SyncEventStream {
+    sender: broadcast::Sender<SyncEvent>,
+}
+
+impl SyncEventStream {
+    pub fn new() -> Self {
+        let (sender, _receiver) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Subscribes to this node's peer and sync-state events.
+    pub fn subscribe(&self) -> broadcast::Receiver<SyncEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Publishes `event` to every current subscriber.
+    ///
+    /// There being no subscribers yet (e.g. a topology test with `consensus_setup: None`) is a
+    /// legitimate state, not an error, so a failed send is ignored.
+    fn publish(&self, event: SyncEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn notify_peer_connected(&self, addr: SocketAddr) {
+        self.publish(SyncEvent::Peer(PeerEvent::PeerConnected(addr)));
+    }
+
+    pub fn notify_peer_disconnected(&self, addr: SocketAddr) {
+        self.publish(SyncEvent::Peer(PeerEvent::PeerDisconnected(addr)));
+    }
+
+    pub fn notify_state_changed(&self, state: SyncState) {
+        self.publish(SyncEvent::StateChanged(state));
+    }
+}
+
+impl Default for SyncEventStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscribers_observe_the_exact_event_sequence() {
+        let stream = SyncEventStream::new();
+        let mut subscriber = stream.subscribe();
+
+        let addr: SocketAddr = "127.0.0.1:4141".parse().unwrap();
+        stream.notify_peer_connected(addr);
+        stream.notify_state_changed(SyncState::Syncing);
+        stream.notify_peer_disconnected(addr);
+
+        assert_eq!(
+            subscriber.try_recv().unwrap(),
+            SyncEvent::Peer(PeerEvent::PeerConnected(addr))
+        );
+        assert_eq!(subscriber.try_recv().unwrap(), SyncEvent::StateChanged(SyncState::Syncing));
+        assert_eq!(
+            subscriber.try_recv().unwrap(),
+            SyncEvent::Peer(PeerEvent::PeerDisconnected(addr))
+        );
+        assert!(subscriber.try_recv().is_err());
+    }
+
+    #[test]
+    fn publishing_with_no_subscribers_does_not_panic() {
+        let stream = SyncEventStream::new();
+        stream.notify_state_changed(SyncState::Idle);
+    }
+
+    #[test]
+    fn independent_subscribers_each_see_every_event() {
+        let stream = SyncEventStream::new();
+        let mut a = stream.subscribe();
+        let mut b = stream.subscribe();
+
+        let addr: SocketAddr = "127.0.0.1:4142".parse().unwrap();
+        stream.notify_peer_connected(addr);
+
+        assert_eq!(a.try_recv().unwrap(), SyncEvent::Peer(PeerEvent::PeerConnected(addr)));
+        assert_eq!(b.try_recv().unwrap(), SyncEvent::Peer(PeerEvent::PeerConnected(addr)));
+    }
+}
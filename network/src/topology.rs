@@ -0,0 +1,300 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Graph-level metrics describing the shape of the peer-to-peer overlay.
+
+use std::{collections::VecDeque, net::SocketAddr};
+
+use crate::Node;
+
+/// Returns the density of a graph with `node_count` nodes and `edge_count` edges, i.e. the ratio
+/// of actual connections to the number of connections a complete graph of the same size would
+/// have.
+pub fn calculate_density(node_count: f64, edge_count: f64) -> f64 {
+    if node_count < 2.0 {
+        return 0.0;
+    }
+
+    let max_edges = node_count * (node_count - 1.0) / 2.0;
+    edge_count / max_edges
+}
+
+/// A snapshot of the peer graph, used to compute centrality and density measures over it.
+///
+/// Built from the undirected edge list implied by every node's `PeerBook`: an edge `(i, j)`
+/// means nodes `i` and `j` are connected to each other. Indices are positions into whatever node
+/// list the caller built the graph from.
+pub struct NetworkMetrics {
+    node_count: usize,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl NetworkMetrics {
+    /// Builds the adjacency lists for a graph of `node_count` nodes from an undirected edge list.
+    pub fn new(node_count: usize, edges: &[(usize, usize)]) -> Self {
+        let mut adjacency = vec![Vec::new(); node_count];
+
+        for &(a, b) in edges {
+            if a == b || a >= node_count || b >= node_count {
+                continue;
+            }
+
+            if !adjacency[a].contains(&b) {
+                adjacency[a].push(b);
+            }
+            if !adjacency[b].contains(&a) {
+                adjacency[b].push(a);
+            }
+        }
+
+        Self { node_count, adjacency }
+    }
+
+    /// Builds the graph directly from a live node slice, so operators and tests can get
+    /// density/centrality measures without hand-rolling an edge list themselves.
+    ///
+    /// Nodes are indexed by their position in `nodes`; an edge `(i, j)` is added whenever `i` and
+    /// `j` have each other's `local_address` in their `peer_book`'s active peers.
+    pub fn from_nodes<S>(nodes: &[Node<S>]) -> Self {
+        let addresses: Vec<Option<SocketAddr>> = nodes.iter().map(|node| node.local_address()).collect();
+
+        let active: Vec<Vec<SocketAddr>> = nodes
+            .iter()
+            .map(|node| node.peer_book.lock().active_peer_addresses().collect())
+            .collect();
+
+        let mut edges = Vec::new();
+        for (i, peers) in active.iter().enumerate() {
+            for peer_addr in peers {
+                if let Some(j) = addresses.iter().position(|addr| *addr == Some(*peer_addr)) {
+                    let mutual = addresses[i].map_or(false, |addr_i| active[j].contains(&addr_i));
+                    if i < j && mutual {
+                        edges.push((i, j));
+                    }
+                }
+            }
+        }
+
+        Self::new(nodes.len(), &edges)
+    }
+
+    /// The number of edges in the graph.
+    fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(|neighbors| neighbors.len()).sum::<usize>() / 2
+    }
+
+    /// The overlay's density: actual connections over the number a complete graph would have.
+    pub fn density(&self) -> f64 {
+        calculate_density(self.node_count as f64, self.edge_count() as f64)
+    }
+
+    /// The degree (connection count) of every node, i.e. its degree centrality unnormalized.
+    pub fn degree_centrality(&self) -> Vec<u32> {
+        self.adjacency.iter().map(|neighbors| neighbors.len() as u32).collect()
+    }
+
+    /// Closeness centrality of every node: the reciprocal of the sum of its shortest-path
+    /// distances to every other reachable node, computed via a BFS from each node in turn.
+    ///
+    /// A node that is unreachable from some nodes only has its distance to the nodes it *can*
+    /// reach counted, so isolated components don't collapse every score to zero.
+    pub fn closeness_centrality(&self) -> Vec<f64> {
+        (0..self.node_count)
+            .map(|source| {
+                let distances = self.bfs_distances(source);
+                let total: u32 = distances.iter().filter_map(|d| *d).sum();
+
+                if total == 0 { 0.0 } else { 1.0 / total as f64 }
+            })
+            .collect()
+    }
+
+    /// Betweenness centrality of every node, computed with Brandes' algorithm: a BFS from each
+    /// source accumulating, for every node, the fraction of shortest paths between other pairs
+    /// that pass through it.
+    pub fn betweenness_centrality(&self) -> Vec<f64> {
+        let n = self.node_count;
+        let mut betweenness = vec![0.0; n];
+
+        for source in 0..n {
+            let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+            let mut sigma = vec![0.0_f64; n];
+            let mut distance = vec![-1_i64; n];
+            let mut order = Vec::with_capacity(n);
+            let mut queue = VecDeque::new();
+
+            sigma[source] = 1.0;
+            distance[source] = 0;
+            queue.push_back(source);
+
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+
+                for &w in &self.adjacency[v] {
+                    if distance[w] < 0 {
+                        distance[w] = distance[v] + 1;
+                        queue.push_back(w);
+                    }
+
+                    if distance[w] == distance[v] + 1 {
+                        sigma[w] += sigma[v];
+                        predecessors[w].push(v);
+                    }
+                }
+            }
+
+            let mut dependency = vec![0.0_f64; n];
+            for &w in order.iter().rev() {
+                for &v in &predecessors[w] {
+                    dependency[v] += (sigma[v] / sigma[w]) * (1.0 + dependency[w]);
+                }
+
+                if w != source {
+                    betweenness[w] += dependency[w];
+                }
+            }
+        }
+
+        // Every shortest path between an unordered pair {s, t} is counted once from s's BFS and
+        // once from t's BFS, so each node's accumulated dependency is double its true score.
+        for value in &mut betweenness {
+            *value /= 2.0;
+        }
+
+        betweenness
+    }
+
+    /// Eigenvector centrality of every node, computed via power iteration on the adjacency
+    /// matrix until the scores converge (or a generous iteration cap is hit).
+    ///
+    /// Scores are L2-normalized so they're comparable across graphs of different sizes.
+    pub fn eigenvector_centrality(&self) -> Vec<f64> {
+        let n = self.node_count;
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let mut scores = vec![1.0 / (n as f64).sqrt(); n];
+
+        for _ in 0..200 {
+            let mut next = vec![0.0; n];
+            for (node, neighbors) in self.adjacency.iter().enumerate() {
+                for &neighbor in neighbors {
+                    next[neighbor] += scores[node];
+                }
+            }
+
+            let norm = next.iter().map(|v| v * v).sum::<f64>().sqrt();
+            if norm == 0.0 {
+                return next;
+            }
+            for value in &mut next {
+                *value /= norm;
+            }
+
+            let delta: f64 = scores
+                .iter()
+                .zip(next.iter())
+                .map(|(old, new)| (old - new).abs())
+                .sum();
+
+            scores = next;
+            if delta < 1e-10 {
+                break;
+            }
+        }
+
+        scores
+    }
+
+    /// BFS shortest-path distances from `source` to every node, `None` where unreachable.
+    fn bfs_distances(&self, source: usize) -> Vec<Option<u32>> {
+        let mut distances = vec![None; self.node_count];
+        distances[source] = Some(0);
+
+        let mut queue = VecDeque::new();
+        queue.push_back(source);
+
+        while let Some(node) = queue.pop_front() {
+            let current = distances[node].unwrap();
+
+            for &neighbor in &self.adjacency[node] {
+                if distances[neighbor].is_none() {
+                    distances[neighbor] = Some(current + 1);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        distances
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn density_of_a_complete_graph_is_one() {
+        let edges = [(0, 1), (0, 2), (1, 2)];
+        let metrics = NetworkMetrics::new(3, &edges);
+        assert!((metrics.density() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn degree_centrality_matches_adjacency() {
+        // A star: node 0 is the hub.
+        let edges = [(0, 1), (0, 2), (0, 3)];
+        let metrics = NetworkMetrics::new(4, &edges);
+        assert_eq!(metrics.degree_centrality(), vec![3, 1, 1, 1]);
+    }
+
+    #[test]
+    fn hub_has_highest_closeness_and_betweenness_in_a_star() {
+        let edges = [(0, 1), (0, 2), (0, 3), (0, 4)];
+        let metrics = NetworkMetrics::new(5, &edges);
+
+        let closeness = metrics.closeness_centrality();
+        assert!(closeness[0] > closeness[1]);
+
+        let betweenness = metrics.betweenness_centrality();
+        assert!(betweenness[0] > betweenness[1]);
+        // Leaves never sit on a shortest path between any other pair.
+        assert_eq!(betweenness[1], 0.0);
+    }
+
+    #[test]
+    fn eigenvector_centrality_favors_well_connected_nodes() {
+        let edges = [(0, 1), (0, 2), (0, 3), (1, 2)];
+        let metrics = NetworkMetrics::new(4, &edges);
+        let eigenvector = metrics.eigenvector_centrality();
+
+        // Node 0 has the highest degree and is connected to the other high-degree nodes.
+        assert!(eigenvector[0] > eigenvector[3]);
+    }
+
+    #[test]
+    fn line_graph_betweenness_peaks_in_the_middle() {
+        let edges = [(0, 1), (1, 2), (2, 3), (3, 4)];
+        let metrics = NetworkMetrics::new(5, &edges);
+        let betweenness = metrics.betweenness_centrality();
+
+        assert!(betweenness[2] > betweenness[1]);
+        assert!(betweenness[2] > betweenness[3]);
+        assert_eq!(betweenness[0], 0.0);
+        assert_eq!(betweenness[4], 0.0);
+    }
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use snarkos_network::{topology::calculate_density, Node};
+use snarkos_network::{topology::NetworkMetrics, Node};
 use snarkos_storage::LedgerStorage;
 use snarkos_testing::{
     network::{
@@ -69,12 +69,12 @@ async fn spawn_nodes_in_a_line() {
     start_nodes(&nodes).await;
 
     // First and Last nodes should have 1 connected peer.
-    wait_until!(5, nodes.first().unwrap().peer_book.get_active_peer_count() == 1);
-    wait_until!(5, nodes.last().unwrap().peer_book.get_active_peer_count() == 1);
+    wait_until!(5, nodes.first().unwrap().peer_book.lock().get_active_peer_count() == 1);
+    wait_until!(5, nodes.last().unwrap().peer_book.lock().get_active_peer_count() == 1);
 
     // All other nodes should have two.
     for node in nodes.iter().take(nodes.len() - 1).skip(1) {
-        wait_until!(5, node.peer_book.get_active_peer_count() == 2);
+        wait_until!(5, node.peer_book.lock().get_active_peer_count() == 2);
     }
 }
 
@@ -90,7 +90,7 @@ async fn spawn_nodes_in_a_ring() {
     start_nodes(&nodes).await;
 
     for node in &nodes {
-        wait_until!(5, node.peer_book.get_active_peer_count() == 2);
+        wait_until!(5, node.peer_book.lock().get_active_peer_count() == 2);
     }
 }
 
@@ -106,7 +106,7 @@ async fn spawn_nodes_in_a_star() {
     start_nodes(&nodes).await;
 
     let hub = nodes.first().unwrap();
-    wait_until!(10, hub.peer_book.get_active_peer_count() as usize == N - 1);
+    wait_until!(10, hub.peer_book.lock().get_active_peer_count() as usize == N - 1);
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -214,30 +214,59 @@ async fn star_converges_to_mesh() {
     );
 }
 
-/// Returns the total connection count of the network.
-fn total_connection_count(nodes: &[Node<LedgerStorage>]) -> u32 {
-    let mut count = 0;
+#[tokio::test(flavor = "multi_thread")]
+async fn scale_free_converges_to_mesh() {
+    let setup = TestSetup {
+        consensus_setup: None,
+        peer_sync_interval: 1,
+        min_peers: MIN_PEERS,
+        max_peers: MAX_PEERS,
+        ..Default::default()
+    };
+    let mut nodes = test_nodes(N, setup).await;
+    connect_nodes(&mut nodes, Topology::ScaleFree { m: 3 });
+    start_nodes(&nodes).await;
 
-    for node in nodes {
-        count += node.peer_book.get_connected_peer_count()
-    }
+    wait_until!(10, network_density(&nodes) >= 0.2, 200);
+    wait_until!(
+        10,
+        degree_centrality_delta(&nodes) <= (MAX_PEERS - MIN_PEERS).into(),
+        200
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn small_world_converges_to_mesh() {
+    let setup = TestSetup {
+        consensus_setup: None,
+        peer_sync_interval: 1,
+        min_peers: MIN_PEERS,
+        max_peers: MAX_PEERS,
+        ..Default::default()
+    };
+    let mut nodes = test_nodes(N, setup).await;
+    connect_nodes(&mut nodes, Topology::SmallWorld { k: 3, p: 0.1 });
+    start_nodes(&nodes).await;
 
-    count / 2
+    wait_until!(10, network_density(&nodes) >= 0.2, 200);
+    wait_until!(
+        10,
+        degree_centrality_delta(&nodes) <= (MAX_PEERS - MIN_PEERS).into(),
+        200
+    );
 }
 
-// This could use the degree matrix, though as this is used extensively in tests and checked
-// repeatedly until it reaches a certain value, we want to keep its calculation decoupled from the
-// `NetworkMetrics`.
+/// Returns the delta between the largest and smallest degree centrality in the network, built
+/// from the live peer graph via `NetworkMetrics` rather than an ad-hoc peer count.
 fn degree_centrality_delta(nodes: &[Node<LedgerStorage>]) -> u32 {
-    let dc = nodes.iter().map(|node| node.peer_book.get_connected_peer_count());
-    let min = dc.clone().min().unwrap();
-    let max = dc.max().unwrap();
+    let degrees = NetworkMetrics::from_nodes(nodes).degree_centrality();
+    let min = *degrees.iter().min().unwrap();
+    let max = *degrees.iter().max().unwrap();
 
     max - min
 }
 
-/// Returns the network density.
+/// Returns the network density, computed over the live peer graph via `NetworkMetrics`.
 fn network_density(nodes: &[Node<LedgerStorage>]) -> f64 {
-    let connections = total_connection_count(nodes);
-    calculate_density(nodes.len() as f64, connections as f64)
+    NetworkMetrics::from_nodes(nodes).density()
 }
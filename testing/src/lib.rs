@@ -0,0 +1,40 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+pub mod network;
+
+/// Polls `$condition` every `$interval_ms` milliseconds (default 100ms), for up to `$limit_secs`
+/// seconds, panicking if it never becomes true.
+#[macro_export]
+macro_rules! wait_until {
+    ($limit_secs:expr, $condition:expr) => {
+        $crate::wait_until!($limit_secs, $condition, 100);
+    };
+    ($limit_secs:expr, $condition:expr, $interval_ms:expr) => {
+        let now = std::time::Instant::now();
+        loop {
+            if $condition {
+                break;
+            }
+
+            if now.elapsed() > std::time::Duration::from_secs($limit_secs) {
+                panic!("timed out waiting for condition: {}", stringify!($condition));
+            }
+
+            tokio::time::sleep(std::time::Duration::from_millis($interval_ms)).await;
+        }
+    };
+}
@@ -0,0 +1,63 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+pub mod topology;
+
+use snarkos_network::Environment;
+
+/// The consensus setup a test node should be started with; `None` keeps peering tests from
+/// dragging in a whole ledger/consensus stack they don't need.
+#[derive(Debug, Clone)]
+pub struct ConsensusSetup {
+    pub block_sync_interval: u64,
+}
+
+/// Everything a test needs to spin up a [`snarkos_network::Node`] with a particular
+/// configuration.
+#[derive(Debug, Clone)]
+pub struct TestSetup {
+    pub consensus_setup: Option<ConsensusSetup>,
+    pub peer_sync_interval: u64,
+    pub min_peers: u16,
+    pub max_peers: u16,
+    pub is_bootnode: bool,
+    /// The minimum interval, in milliseconds, between two successfully accepted inbound
+    /// connections. Defaults to `0` (no throttling) so most tests can ignore it; set it to
+    /// exercise the restrictive end of the inbound rate limiter.
+    pub min_inbound_connection_interval_ms: u64,
+    /// The minimum interval, in milliseconds, between two failed inbound handshake attempts.
+    pub min_inbound_failure_interval_ms: u64,
+}
+
+impl Default for TestSetup {
+    fn default() -> Self {
+        Self {
+            consensus_setup: None,
+            peer_sync_interval: 1,
+            min_peers: 1,
+            max_peers: 100,
+            is_bootnode: false,
+            min_inbound_connection_interval_ms: 0,
+            min_inbound_failure_interval_ms: 0,
+        }
+    }
+}
+
+/// Builds the [`Environment`] a node should be constructed with for the given `setup`.
+pub fn test_config(setup: TestSetup) -> Environment {
+    Environment::new(None, setup.min_peers, setup.max_peers, setup.is_bootnode)
+        .with_inbound_rate_limits(setup.min_inbound_connection_interval_ms, setup.min_inbound_failure_interval_ms)
+}
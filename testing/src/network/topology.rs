@@ -0,0 +1,308 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Synthetic overlay shapes used to seed peer connections in topology tests.
+
+use std::collections::HashSet;
+
+use rand::{seq::SliceRandom, Rng};
+use snarkos_network::Node;
+use snarkos_storage::LedgerStorage;
+
+/// The shape of the overlay a test harness should wire its nodes up in before starting them.
+// `SmallWorld`'s rewiring probability is an `f64`, which isn't `Eq`, so this type can only derive
+// `PartialEq`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Topology {
+    /// Each node connects only to the next one: `0 - 1 - 2 - ... - n`.
+    Line,
+    /// Like [`Topology::Line`], but the last node also connects back to the first.
+    Ring,
+    /// Every node connects to node `0`.
+    Star,
+    /// Every node connects to every other node.
+    Mesh,
+    /// A Barabási–Albert scale-free graph: starting from a small seed clique, each new node
+    /// attaches with `m` edges chosen with probability proportional to the existing nodes'
+    /// degree, producing the hub-and-spoke degree distribution real gossip overlays tend toward.
+    ScaleFree {
+        /// The number of edges each newly added node attaches with.
+        m: usize,
+    },
+    /// A Watts–Strogatz small-world graph: starts from a ring lattice where each node connects
+    /// to its `k` nearest neighbors, then rewires each edge with probability `p` to a random
+    /// target, trading a bit of the lattice's clustering for the ring's short path lengths.
+    SmallWorld {
+        /// The number of nearest neighbors (on each side) every node starts out connected to.
+        k: usize,
+        /// The probability of rewiring any given lattice edge to a random target.
+        p: f64,
+    },
+}
+
+/// Wires up `nodes` into the given `topology` by recording each connection in both ends' peer
+/// books; no connection is dialed, before or after the nodes are started.
+pub fn connect_nodes(nodes: &mut [Node<LedgerStorage>], topology: Topology) {
+    match topology {
+        Topology::Line => {
+            for i in 0..nodes.len().saturating_sub(1) {
+                connect_pair(nodes, i, i + 1);
+            }
+        }
+        Topology::Ring => {
+            for i in 0..nodes.len().saturating_sub(1) {
+                connect_pair(nodes, i, i + 1);
+            }
+            if nodes.len() > 2 {
+                connect_pair(nodes, nodes.len() - 1, 0);
+            }
+        }
+        Topology::Star => {
+            for i in 1..nodes.len() {
+                connect_pair(nodes, 0, i);
+            }
+        }
+        Topology::Mesh => {
+            for i in 0..nodes.len() {
+                for j in (i + 1)..nodes.len() {
+                    connect_pair(nodes, i, j);
+                }
+            }
+        }
+        Topology::ScaleFree { m } => {
+            for (i, j) in scale_free_edges(nodes.len(), m) {
+                connect_pair(nodes, i, j);
+            }
+        }
+        Topology::SmallWorld { k, p } => {
+            for (i, j) in small_world_edges(nodes.len(), k, p) {
+                connect_pair(nodes, i, j);
+            }
+        }
+    }
+}
+
+/// Generates the edge list for a Barabási–Albert preferential-attachment graph of `n` nodes,
+/// where each node beyond the seed clique attaches with `m` edges.
+///
+/// Nodes `0..=m` form the seed clique (there must be at least `m + 1` nodes for every new node to
+/// find `m` distinct targets). Each subsequent node picks its `m` targets from the nodes added so
+/// far, weighted by how many edges they already have, via a repeated-node sampling array: every
+/// existing edge endpoint is entered into the array once per edge, so higher-degree nodes appear
+/// more often and are proportionally more likely to be drawn.
+fn scale_free_edges(n: usize, m: usize) -> Vec<(usize, usize)> {
+    let m = m.max(1);
+    if n <= m {
+        return (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (i, j))).collect();
+    }
+
+    let mut edges = Vec::new();
+    let mut targets = Vec::new();
+
+    // Seed clique: nodes `0..=m` are all connected to each other.
+    for i in 0..=m {
+        for j in (i + 1)..=m {
+            edges.push((i, j));
+            targets.push(i);
+            targets.push(j);
+        }
+    }
+
+    let mut rng = rand::thread_rng();
+    for new_node in (m + 1)..n {
+        let mut chosen = HashSet::new();
+        while chosen.len() < m {
+            let candidate = *targets.choose(&mut rng).unwrap();
+            chosen.insert(candidate);
+        }
+
+        for target in chosen {
+            edges.push((new_node, target));
+            targets.push(new_node);
+            targets.push(target);
+        }
+    }
+
+    edges
+}
+
+/// Generates the edge list for a Watts–Strogatz small-world graph of `n` nodes: a ring lattice
+/// where each node connects to its `k` nearest neighbors on each side, with every edge then
+/// rewired to a random, distinct target with probability `p`.
+///
+/// Which lattice pairs get rewired is decided for the whole graph up front, rather than while
+/// walking the lattice edge by edge: a rewire target chosen on the fly could otherwise land on a
+/// pair the walk hasn't reached yet, and when it later got there that pair would already be
+/// "seen" and get silently dropped instead of kept, leaving the graph short of edges.
+fn small_world_edges(n: usize, k: usize, p: f64) -> Vec<(usize, usize)> {
+    if n < 3 {
+        return (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (i, j))).collect();
+    }
+
+    let k = k.max(1).min((n - 1) / 2);
+    let mut rng = rand::thread_rng();
+
+    let lattice_pairs: Vec<(usize, usize)> = (0..n)
+        .flat_map(|i| {
+            (1..=k).map(move |offset| {
+                let j = (i + offset) % n;
+                if i < j { (i, j) } else { (j, i) }
+            })
+        })
+        .collect();
+
+    // Decide every pair's fate before committing any of them, so a rewire can never collide with
+    // a lattice pair that simply hasn't been visited yet.
+    let mut kept = HashSet::new();
+    let mut to_rewire = Vec::new();
+    for &(a, b) in &lattice_pairs {
+        if rng.gen::<f64>() < p {
+            to_rewire.push(a);
+        } else {
+            kept.insert((a, b));
+        }
+    }
+
+    let mut seen = kept;
+    for a in to_rewire {
+        let target = rewire(n, a, &seen, &mut rng);
+        seen.insert(target);
+    }
+
+    seen.into_iter().collect()
+}
+
+/// Picks a random rewiring target for the edge anchored at `a`, avoiding self-loops and edges
+/// already present in `seen`.
+fn rewire(n: usize, a: usize, seen: &HashSet<(usize, usize)>, rng: &mut impl Rng) -> (usize, usize) {
+    loop {
+        let candidate = rng.gen_range(0..n);
+        if candidate == a {
+            continue;
+        }
+
+        let (x, y) = if a < candidate { (a, candidate) } else { (candidate, a) };
+        if !seen.contains(&(x, y)) {
+            return (x, y);
+        }
+    }
+}
+
+/// Records a connection between `nodes[i]` and `nodes[j]`, to be established once both nodes are
+/// listening.
+fn connect_pair(nodes: &mut [Node<LedgerStorage>], i: usize, j: usize) {
+    // No dialing takes place; the connection is recorded in both ends' peer books so
+    // `NetworkMetrics::from_nodes` can see the edge immediately.
+    let (addr_i, addr_j) = (nodes[i].local_address(), nodes[j].local_address());
+
+    if let (Some(addr_i), Some(addr_j)) = (addr_i, addr_j) {
+        nodes[i].peer_connected(addr_j);
+        nodes[j].peer_connected(addr_i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn degrees(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+        let mut degrees = vec![0; n];
+        for &(a, b) in edges {
+            degrees[a] += 1;
+            degrees[b] += 1;
+        }
+        degrees
+    }
+
+    fn assert_no_self_loops_or_duplicates(edges: &[(usize, usize)]) {
+        let mut seen = HashSet::new();
+        for &(a, b) in edges {
+            assert_ne!(a, b, "edge {:?} is a self-loop", (a, b));
+            let normalized = if a < b { (a, b) } else { (b, a) };
+            assert!(seen.insert(normalized), "edge {:?} appears more than once", normalized);
+        }
+    }
+
+    #[test]
+    fn scale_free_seed_clique_is_fully_connected() {
+        let edges = scale_free_edges(4, 3);
+        assert_no_self_loops_or_duplicates(&edges);
+        // With n == m + 1 there's no room for any node beyond the seed clique, so every pair of
+        // the 4 nodes should be connected.
+        assert_eq!(edges.len(), 4 * 3 / 2);
+    }
+
+    #[test]
+    fn scale_free_every_new_node_attaches_with_m_edges() {
+        let n = 30;
+        let m = 3;
+        let edges = scale_free_edges(n, m);
+        assert_no_self_loops_or_duplicates(&edges);
+
+        let degrees = degrees(n, &edges);
+        // Every node outside the seed clique picked exactly `m` targets when it joined, so its
+        // degree can only grow from there as later nodes attach back to it.
+        for &degree in &degrees[(m + 1)..] {
+            assert!(degree >= m, "expected degree >= {m}, got {degree}");
+        }
+    }
+
+    #[test]
+    fn scale_free_favors_high_degree_hubs() {
+        // Comparing a single early node against a single late one is vulnerable to the
+        // occasional random draw evening the two out, so compare the aggregate degree of the
+        // seed-clique nodes against an equally sized batch of the latest joiners instead: that
+        // margin is governed by the preferential-attachment bias itself, not by one node's luck.
+        let n = 200;
+        let edges = scale_free_edges(n, 2);
+        let degrees = degrees(n, &edges);
+
+        let early: usize = degrees[..10].iter().sum();
+        let late: usize = degrees[(n - 10)..].iter().sum();
+
+        assert!(early > late, "expected early nodes (sum {early}) to out-degree late joiners (sum {late})");
+    }
+
+    #[test]
+    fn small_world_ring_lattice_has_2k_degree_before_rewiring() {
+        let n = 20;
+        let k = 3;
+        let edges = small_world_edges(n, k, 0.0);
+        assert_no_self_loops_or_duplicates(&edges);
+
+        // With no rewiring, every node keeps exactly its `k` neighbors on each side.
+        for degree in degrees(n, &edges) {
+            assert_eq!(degree, 2 * k);
+        }
+    }
+
+    #[test]
+    fn small_world_full_rewiring_still_yields_a_valid_graph() {
+        let n = 20;
+        let edges = small_world_edges(n, 2, 1.0);
+        assert_no_self_loops_or_duplicates(&edges);
+        assert_eq!(edges.len(), n * 2);
+    }
+
+    #[test]
+    fn small_world_partial_rewiring_preserves_edge_count() {
+        let n = 30;
+        let k = 4;
+        let edges = small_world_edges(n, k, 0.3);
+        assert_no_self_loops_or_duplicates(&edges);
+        assert_eq!(edges.len(), n * k);
+    }
+}